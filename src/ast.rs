@@ -0,0 +1,44 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+
+use crate::lexer::Token;
+
+/// A parsed Lox expression. `Display` renders the canonical fully
+/// parenthesized form used by the `parse` command, e.g.
+/// `(* (- 5) (group 4.2))`.
+pub(crate) enum Expr<'src> {
+    Literal(Token<'src>),
+    Unary {
+        op: Token<'src>,
+        rhs: Box<Expr<'src>>,
+    },
+    Binary {
+        lhs: Box<Expr<'src>>,
+        op: Token<'src>,
+        rhs: Box<Expr<'src>>,
+    },
+    Grouping(Box<Expr<'src>>),
+    Variable(&'src str),
+}
+
+impl Display for Expr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Literal(tok) => write!(f, "{}", literal_text(tok)),
+            Expr::Unary { op, rhs } => write!(f, "({} {})", op.lexeme(), rhs),
+            Expr::Binary { lhs, op, rhs } => write!(f, "({} {} {})", op.lexeme(), lhs, rhs),
+            Expr::Grouping(expr) => write!(f, "(group {})", expr),
+            Expr::Variable(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Text for a literal token as it appears inside the parenthesized form:
+/// numbers keep their original source spelling rather than being
+/// re-rendered through `f64`'s `Debug` (which would turn `5` into `5.0`).
+fn literal_text<'src>(tok: &Token<'src>) -> Cow<'src, str> {
+    match tok {
+        Token::Number(_, s) => Cow::Borrowed(*s),
+        _ => tok.tok_print(),
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::lexer::Loc;
+
+/// What went wrong, independent of where it happened. `Diagnostic` pairs
+/// this with a `Loc` so rendering can wait until every diagnostic from a
+/// lex/parse run has been collected.
+#[derive(Debug, Clone)]
+pub(crate) enum DiagnosticKind {
+    UnexpectedCharacter(char),
+    UnterminatedString,
+    InvalidNumber,
+    UnknownEscape(char),
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticKind::UnexpectedCharacter(c) => write!(f, "Unexpected character: {}", c),
+            DiagnosticKind::UnterminatedString => write!(f, "Unterminated string."),
+            DiagnosticKind::InvalidNumber => write!(f, "Invalid number."),
+            DiagnosticKind::UnknownEscape(c) => write!(f, "Unknown escape sequence: \\{}", c),
+        }
+    }
+}
+
+/// A single collected error, carrying the `Loc` of the token it was raised
+/// at so it can be rendered with source context after the fact instead of
+/// being printed inline as it's found.
+#[derive(Debug, Clone)]
+pub(crate) struct Diagnostic {
+    pub(crate) kind: DiagnosticKind,
+    pub(crate) loc: Loc,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(kind: DiagnosticKind, loc: Loc) -> Self {
+        Self { kind, loc }
+    }
+
+    /// Renders the offending source line with a `^` under the column the
+    /// diagnostic was raised at, e.g.:
+    /// ```text
+    /// [line 3] Error: Unterminated string.
+    /// 3 | var x = "oops
+    ///   |         ^ Unterminated string.
+    /// ```
+    fn render(&self, src: &str) -> String {
+        let line_text = src.lines().nth(self.loc.line.saturating_sub(1)).unwrap_or("");
+        let line_num = self.loc.line.to_string();
+        let gutter = format!("{} | ", line_num);
+        let margin = format!("{} | ", " ".repeat(line_num.len()));
+        let indent = " ".repeat(self.loc.column.saturating_sub(1));
+        format!(
+            "[line {}] Error: {}\n{gutter}{line_text}\n{margin}{indent}^ {}",
+            self.loc.line,
+            self.kind,
+            self.kind,
+        )
+    }
+}
+
+/// Accumulates diagnostics from lexing/parsing instead of printing them
+/// inline, so a run can surface every problem it finds rather than bailing
+/// at the first one, and so callers embedding the crate can inspect the
+/// collected `Vec<Diagnostic>` themselves.
+#[derive(Debug, Default)]
+pub(crate) struct Reporter {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Reporter {
+    pub(crate) fn report(&mut self, kind: DiagnosticKind, loc: Loc) {
+        self.diagnostics.push(Diagnostic::new(kind, loc));
+    }
+
+    pub(crate) fn has_errors(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+
+    /// Drops every collected diagnostic, keeping the backing `Vec`'s
+    /// allocation so a caller (e.g. a REPL) can reuse one `Reporter` across
+    /// many lexer/parser runs instead of allocating a fresh one each time.
+    pub(crate) fn clear(&mut self) {
+        self.diagnostics.clear();
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Prints every collected diagnostic against `src` to stderr. A no-op
+    /// if nothing was collected.
+    pub(crate) fn print(&self, src: &str) {
+        for diagnostic in &self.diagnostics {
+            eprintln!("{}", diagnostic.render(src));
+        }
+    }
+
+    /// Like `print`, but exits with 65, Lox's convention for a compile-time
+    /// error, if anything was collected.
+    pub(crate) fn exit_if_errors(&self, src: &str) {
+        if !self.has_errors() {
+            return;
+        }
+        self.print(src);
+        std::process::exit(65);
+    }
+}
@@ -0,0 +1,522 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::str::CharIndices;
+
+use phf::phf_map;
+
+use crate::diagnostics::{DiagnosticKind, Reporter};
+
+static KEYWORDS: phf::Map<&'static str, Token<'static>> = phf_map! {
+    "and" => Token::And,
+    "class" => Token::Class,
+    "else" => Token::Else,
+    "false" => Token::False,
+    "for" => Token::For,
+    "fun" => Token::Fun,
+    "if" => Token::If,
+    "nil" => Token::Nil,
+    "or" => Token::Or,
+    "print" => Token::Print,
+    "return" => Token::Return,
+    "super" => Token::Super,
+    "this" => Token::This,
+    "true" => Token::True,
+    "var" => Token::Var,
+    "while" => Token::While,
+};
+
+#[derive(Clone)]
+pub(crate) enum Token<'src> {
+    Eof,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Var,
+    Ident(&'src str),
+    /// The decoded value (escapes resolved) and the raw source slice between
+    /// the quotes (escapes intact), mirroring `Number`'s value/spelling pair.
+    String(Cow<'src, str>, &'src str),
+    Semicolon,
+    Star,
+    Dot,
+    Comma,
+    Plus,
+    Minus,
+    Equal,
+    EqualEqual,
+    Bang,
+    BangEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Slash,
+    Number(f64, &'src str),
+    And,
+    Class,
+    Else,
+    False,
+    For,
+    Fun,
+    If,
+    Nil,
+    Or,
+    Print,
+    Return,
+    Super,
+    This,
+    True,
+    While,
+}
+
+impl<'src> Token<'src> {
+    fn token_type(&self) -> &'static str {
+        match self {
+            Token::Eof => "EOF",
+            Token::LParen => "LEFT_PAREN",
+            Token::RParen => "RIGHT_PAREN",
+            Token::LBrace => "LEFT_BRACE",
+            Token::RBrace => "RIGHT_BRACE",
+            Token::Ident(_) => "IDENTIFIER",
+            Token::String(_, _) => "STRING",
+            Token::Semicolon => "SEMICOLON",
+            Token::Star => "STAR",
+            Token::Dot => "DOT",
+            Token::Comma => "COMMA",
+            Token::Plus => "PLUS",
+            Token::Minus => "MINUS",
+            Token::Equal => "EQUAL",
+            Token::EqualEqual => "EQUAL_EQUAL",
+            Token::Bang => "BANG",
+            Token::BangEqual => "BANG_EQUAL",
+            Token::Less => "LESS",
+            Token::LessEqual => "LESS_EQUAL",
+            Token::Greater => "GREATER",
+            Token::GreaterEqual => "GREATER_EQUAL",
+            Token::Slash => "SLASH",
+            Token::Number(_, _) => "NUMBER",
+            Token::And => "AND",
+            Token::Class => "CLASS",
+            Token::Else => "ELSE",
+            Token::False => "FALSE",
+            Token::For => "FOR",
+            Token::Fun => "FUN",
+            Token::If => "IF",
+            Token::Nil => "NIL",
+            Token::Or => "OR",
+            Token::Print => "PRINT",
+            Token::Return => "RETURN",
+            Token::Super => "SUPER",
+            Token::This => "THIS",
+            Token::True => "TRUE",
+            Token::Var => "VAR",
+            Token::While => "WHILE",
+        }
+    }
+
+    /// The token's source text. Borrowed directly from the source for every
+    /// variant except `String`, which has to splice the surrounding quotes
+    /// back around the (already-borrowed) string content.
+    pub(crate) fn lexeme(&self) -> Cow<'src, str> {
+        match self {
+            Token::Eof => Cow::Borrowed(""),
+            Token::LParen => Cow::Borrowed("("),
+            Token::RParen => Cow::Borrowed(")"),
+            Token::LBrace => Cow::Borrowed("{"),
+            Token::RBrace => Cow::Borrowed("}"),
+            Token::Ident(s) => Cow::Borrowed(s),
+            Token::String(_, raw) => Cow::Owned(format!(r#""{}""#, raw)),
+            Token::Semicolon => Cow::Borrowed(";"),
+            Token::Star => Cow::Borrowed("*"),
+            Token::Dot => Cow::Borrowed("."),
+            Token::Comma => Cow::Borrowed(","),
+            Token::Plus => Cow::Borrowed("+"),
+            Token::Minus => Cow::Borrowed("-"),
+            Token::Equal => Cow::Borrowed("="),
+            Token::EqualEqual => Cow::Borrowed("=="),
+            Token::Bang => Cow::Borrowed("!"),
+            Token::BangEqual => Cow::Borrowed("!="),
+            Token::Less => Cow::Borrowed("<"),
+            Token::LessEqual => Cow::Borrowed("<="),
+            Token::Greater => Cow::Borrowed(">"),
+            Token::GreaterEqual => Cow::Borrowed(">="),
+            Token::Slash => Cow::Borrowed("/"),
+            Token::Number(_, s) => Cow::Borrowed(s),
+            Token::And => Cow::Borrowed("and"),
+            Token::Class => Cow::Borrowed("class"),
+            Token::Else => Cow::Borrowed("else"),
+            Token::False => Cow::Borrowed("false"),
+            Token::For => Cow::Borrowed("for"),
+            Token::Fun => Cow::Borrowed("fun"),
+            Token::If => Cow::Borrowed("if"),
+            Token::Nil => Cow::Borrowed("nil"),
+            Token::Or => Cow::Borrowed("or"),
+            Token::Print => Cow::Borrowed("print"),
+            Token::Return => Cow::Borrowed("return"),
+            Token::Super => Cow::Borrowed("super"),
+            Token::This => Cow::Borrowed("this"),
+            Token::True => Cow::Borrowed("true"),
+            Token::Var => Cow::Borrowed("var"),
+            Token::While => Cow::Borrowed("while"),
+        }
+    }
+
+    /// Like `lexeme`, but strings print their content unquoted and numbers
+    /// print via `f64`'s `Debug` rather than their raw source spelling.
+    pub(crate) fn tok_print(&self) -> Cow<'src, str> {
+        match self {
+            Token::String(value, _) => value.clone(),
+            Token::Number(n, _) => Cow::Owned(format!("{:?}", n)),
+            _ => self.lexeme(),
+        }
+    }
+
+    fn literal(&self) -> Cow<'src, str> {
+        match self {
+            Token::String(value, _) => value.clone(),
+            Token::Number(n, _) => Cow::Owned(format!("{:?}", n)),
+            _ => Cow::Borrowed("null"),
+        }
+    }
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.token_type(),
+            self.lexeme(),
+            self.literal()
+        )
+    }
+}
+
+/// A location in the source: a 1-based `line`/`column` together with the
+/// byte offset range (`start..end`) it covers. Used both as a span (e.g. the
+/// bytes of a token's first character) and as a single point, in which case
+/// `start == end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Loc {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// A token together with the locations of its first and last character,
+/// mirroring the conventional `Spanned<Token, Loc, Error>` triple.
+pub(crate) type Spanned<'src> = (Loc, Token<'src>, Loc);
+
+pub(crate) struct Lexer<'src> {
+    src: &'src str,
+    chars: CharIndices<'src>,
+    index: usize,
+    line: usize,
+    column: usize,
+    pub(crate) reporter: Reporter,
+    done: bool,
+    /// Tokens already pulled from `read_token` but not yet handed to the
+    /// caller, so `peek_nth` can look further ahead than `peek_token`.
+    buf: VecDeque<Spanned<'src>>,
+}
+
+impl<'src> Lexer<'src> {
+    pub(crate) fn new(src: &'src str) -> Self {
+        Self::with_reporter(src, Reporter::default())
+    }
+
+    /// Like `new`, but reuses an existing `Reporter` (cleared of whatever it
+    /// previously collected) instead of allocating a fresh one — lets a REPL
+    /// lex many lines without a new `Reporter` per line.
+    pub(crate) fn with_reporter(src: &'src str, mut reporter: Reporter) -> Self {
+        reporter.clear();
+        Self {
+            src,
+            chars: src.char_indices(),
+            index: 0,
+            line: 1,
+            column: 0,
+            reporter,
+            done: false,
+            buf: VecDeque::new(),
+        }
+    }
+}
+
+impl<'src> Lexer<'src> {
+    fn loc(&self) -> Loc {
+        Loc {
+            line: self.line,
+            column: self.column,
+            start: self.index,
+            end: self.index,
+        }
+    }
+
+    fn peek_char(&mut self) -> Option<char> {
+        self.chars.clone().next().map(|(_, c)| c)
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        let c = self.chars.next().map(|(_, c)| c)?;
+        self.index += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Undoes a single `read_char`, as used when a lookahead char turns out
+    /// not to belong to the token being scanned. Only ever called to put
+    /// back a single-byte ASCII char (digit, letter, underscore or `.`), and
+    /// never a `\n`, so stepping `column`/`index` back by one byte and
+    /// re-deriving `chars` from that offset is always correct.
+    fn unread_char(&mut self) {
+        self.index -= 1;
+        self.column -= 1;
+        self.chars = self.src[self.index..].char_indices();
+    }
+
+    fn read_number(&mut self) -> Option<&'src str> {
+        let start = self.index;
+        let mut had_dot = false;
+        while let Some(c) = self.peek_char() {
+            if c.is_ascii_digit() {
+                self.read_char();
+            } else if c == '.' && !had_dot {
+                self.read_char();
+                had_dot = true;
+            } else {
+                break;
+            }
+        }
+
+        if start == self.index {
+            None
+        } else {
+            if self.src.as_bytes()[self.index - 1] == b'.' {
+                self.unread_char();
+            }
+            Some(&self.src[start..self.index])
+        }
+    }
+
+    fn read_ident(&mut self) -> Option<&'src str> {
+        let start = self.index;
+        while let Some('a'..='z' | 'A'..='Z' | '0'..='9' | '_') = self.peek_char() {
+            self.read_char();
+        }
+
+        if start == self.index {
+            None
+        } else {
+            Some(&self.src[start..self.index])
+        }
+    }
+
+    fn read_token(&mut self) -> Spanned<'src> {
+        'main_lex: loop {
+            let Some(c) = self.read_char() else {
+                self.done = true;
+                let eof = self.loc();
+                return (eof, Token::Eof, eof);
+            };
+
+            // Captured after consuming `c` so `column`/`start` already point
+            // at it (columns are 1-based; `start..end` covers `c`'s bytes).
+            let start = Loc {
+                line: self.line,
+                column: self.column,
+                start: self.index - c.len_utf8(),
+                end: self.index,
+            };
+
+            let tok = match c {
+                '(' => Token::LParen,
+                ')' => Token::RParen,
+                '{' => Token::LBrace,
+                '}' => Token::RBrace,
+                ';' => Token::Semicolon,
+                '*' => Token::Star,
+                '.' => Token::Dot,
+                ',' => Token::Comma,
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '=' => match self.peek_char() {
+                    Some('=') => {
+                        self.read_char();
+                        Token::EqualEqual
+                    }
+                    _ => Token::Equal,
+                },
+                '!' => match self.peek_char() {
+                    Some('=') => {
+                        self.read_char();
+                        Token::BangEqual
+                    }
+                    _ => Token::Bang,
+                },
+                '<' => match self.peek_char() {
+                    Some('=') => {
+                        self.read_char();
+                        Token::LessEqual
+                    }
+                    _ => Token::Less,
+                },
+                '>' => match self.peek_char() {
+                    Some('=') => {
+                        self.read_char();
+                        Token::GreaterEqual
+                    }
+                    _ => Token::Greater,
+                },
+                '/' => match self.peek_char() {
+                    Some('/') => {
+                        while let Some(c) = self.read_char() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                        continue 'main_lex;
+                    }
+                    _ => Token::Slash,
+                },
+                '"' => {
+                    let str_start = self.index;
+                    // Only allocated once an escape is actually seen; a string
+                    // with no escapes can keep borrowing straight from `src`.
+                    let mut value: Option<String> = None;
+                    loop {
+                        match self.read_char() {
+                            Some('"') => break,
+                            Some('\\') => {
+                                // Location of the backslash itself, so an
+                                // `UnknownEscape` diagnostic underlines the
+                                // offending escape rather than the opening
+                                // quote.
+                                let escape_loc = self.loc();
+                                let value = value
+                                    .get_or_insert_with(|| self.src[str_start..self.index - 1].to_string());
+                                match self.read_char() {
+                                    Some('n') => value.push('\n'),
+                                    Some('t') => value.push('\t'),
+                                    Some('r') => value.push('\r'),
+                                    Some('"') => value.push('"'),
+                                    Some('\\') => value.push('\\'),
+                                    Some(other) => {
+                                        self.reporter
+                                            .report(DiagnosticKind::UnknownEscape(other), escape_loc);
+                                        value.push('\\');
+                                        value.push(other);
+                                    }
+                                    None => {
+                                        self.reporter
+                                            .report(DiagnosticKind::UnterminatedString, start);
+                                        continue 'main_lex;
+                                    }
+                                }
+                            }
+                            Some(c) => {
+                                if let Some(value) = value.as_mut() {
+                                    value.push(c);
+                                }
+                            }
+                            None => {
+                                self.reporter
+                                    .report(DiagnosticKind::UnterminatedString, start);
+                                continue 'main_lex;
+                            }
+                        }
+                    }
+                    let raw = &self.src[str_start..self.index - 1];
+                    let value = value.map_or(Cow::Borrowed(raw), Cow::Owned);
+                    Token::String(value, raw)
+                }
+                '0'..='9' => {
+                    self.unread_char();
+                    if let Some(num) = self.read_number() {
+                        match num.parse() {
+                            Ok(n) => Token::Number(n, num),
+                            Err(_) => {
+                                self.reporter.report(DiagnosticKind::InvalidNumber, start);
+                                continue 'main_lex;
+                            }
+                        }
+                    } else {
+                        continue 'main_lex;
+                    }
+                }
+                'a'..='z' | 'A'..='Z' | '_' => {
+                    self.unread_char();
+                    if let Some(s) = self.read_ident() {
+                        if let Some(kw) = KEYWORDS.get(s) {
+                            kw.clone()
+                        } else {
+                            Token::Ident(s)
+                        }
+                    } else {
+                        continue 'main_lex;
+                    }
+                }
+                whitespace if whitespace.is_whitespace() => {
+                    continue 'main_lex;
+                }
+                _ => {
+                    self.reporter
+                        .report(DiagnosticKind::UnexpectedCharacter(c), start);
+                    continue 'main_lex;
+                }
+            };
+
+            let end = self.loc();
+            return (start, tok, end);
+        }
+    }
+
+    /// Pulls tokens from `read_token` until `buf` holds at least `n + 1` of
+    /// them. `read_token` keeps handing back `Token::Eof` once the source is
+    /// exhausted (independent of the `done` flag the `Iterator` impl checks), so
+    /// this never needs to special-case running out of input.
+    fn fill(&mut self, n: usize) {
+        while self.buf.len() <= n {
+            let spanned = self.read_token();
+            self.buf.push_back(spanned);
+        }
+    }
+
+    /// Looks at the `n`th upcoming token without consuming it. `peek_nth(0)`
+    /// is the next token `next_token` would return.
+    pub(crate) fn peek_nth(&mut self, n: usize) -> &Spanned<'src> {
+        self.fill(n);
+        &self.buf[n]
+    }
+
+    /// Looks at the next token without consuming it.
+    pub(crate) fn peek_token(&mut self) -> &Spanned<'src> {
+        self.peek_nth(0)
+    }
+
+    /// Consumes and returns the next token, preferring anything already
+    /// buffered by a previous `peek_token`/`peek_nth` call.
+    pub(crate) fn next_token(&mut self) -> Spanned<'src> {
+        self.buf.pop_front().unwrap_or_else(|| self.read_token())
+    }
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Spanned<'src>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            None
+        } else {
+            Some(self.read_token())
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::ast::Expr;
+use crate::diagnostics::Reporter;
+use crate::lexer::{Lexer, Loc, Spanned, Token};
+
+#[derive(Debug)]
+pub(crate) struct ParseError {
+    loc: Loc,
+    message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[line {}] {}", self.loc.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Recursive-descent parser driven by Pratt/precedence-climbing for infix
+/// operators. Looks ahead through the `Lexer`'s own buffered `peek_token`
+/// rather than wrapping it in a `std::iter::Peekable`, since later grammar
+/// (e.g. deciding assignment vs. expression past a leading identifier) needs
+/// more than one token of lookahead, which `Peekable` can't express.
+pub(crate) struct Parser<'src> {
+    lexer: Lexer<'src>,
+}
+
+impl<'src> Parser<'src> {
+    pub(crate) fn new(lexer: Lexer<'src>) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse_expression(&mut self) -> Result<Expr<'src>, ParseError> {
+        let expr = self.parse_expr(0)?;
+
+        let (loc, tok, _) = self.peek();
+        if matches!(tok, Token::Eof) {
+            return Ok(expr);
+        }
+        let loc = *loc;
+        let tok = tok.clone();
+        Err(self.error_at(loc, &tok, "Expect end of expression."))
+    }
+
+    /// Diagnostics collected while lexing, so a caller can report lex
+    /// errors (e.g. an unterminated string) alongside a `ParseError`.
+    pub(crate) fn reporter(&self) -> &Reporter {
+        &self.lexer.reporter
+    }
+
+    /// Takes ownership of the lexer's `Reporter`, leaving a fresh (empty)
+    /// one in its place. Lets a caller (e.g. the REPL) carry one `Reporter`
+    /// across a run of `Parser`s instead of discarding it each time.
+    pub(crate) fn take_reporter(&mut self) -> Reporter {
+        std::mem::take(&mut self.lexer.reporter)
+    }
+
+    fn peek(&mut self) -> &Spanned<'src> {
+        self.lexer.peek_token()
+    }
+
+    fn advance(&mut self) -> Spanned<'src> {
+        self.lexer.next_token()
+    }
+
+    fn error_at(&self, loc: Loc, tok: &Token<'src>, message: &str) -> ParseError {
+        let location = if matches!(tok, Token::Eof) {
+            "end".to_string()
+        } else {
+            format!("'{}'", tok.lexeme())
+        };
+        ParseError {
+            loc,
+            message: format!("Error at {}: {}", location, message),
+        }
+    }
+
+    fn expect_rparen(&mut self) -> Result<(), ParseError> {
+        let (loc, tok, _) = self.peek();
+        let loc = *loc;
+        if matches!(tok, Token::RParen) {
+            self.advance();
+            Ok(())
+        } else {
+            let tok = tok.clone();
+            Err(self.error_at(loc, &tok, "Expect ')' after expression."))
+        }
+    }
+
+    /// `min_bp` is the minimum left binding power an infix operator must have
+    /// to be consumed by this call, which is how precedence climbing nests
+    /// tighter-binding operators below looser ones without explicit grammar
+    /// productions per precedence level.
+    fn parse_expr(&mut self, min_bp: u8) -> Result<Expr<'src>, ParseError> {
+        let mut lhs = self.parse_prefix()?;
+
+        loop {
+            let (_, tok, _) = self.peek();
+            let Some((l_bp, r_bp)) = infix_binding_power(tok) else {
+                break;
+            };
+            if l_bp < min_bp {
+                break;
+            }
+
+            let (_, op, _) = self.advance();
+            let rhs = self.parse_expr(r_bp)?;
+            lhs = Expr::Binary {
+                lhs: Box::new(lhs),
+                op,
+                rhs: Box::new(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr<'src>, ParseError> {
+        let (_, tok, _) = self.peek();
+        if matches!(tok, Token::Bang | Token::Minus) {
+            let (_, op, _) = self.advance();
+            let rhs = self.parse_expr(UNARY_BP)?;
+            return Ok(Expr::Unary {
+                op,
+                rhs: Box::new(rhs),
+            });
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr<'src>, ParseError> {
+        let (loc, tok, _) = self.advance();
+        match tok {
+            Token::Number(_, _) | Token::String(_, _) | Token::True | Token::False | Token::Nil => {
+                Ok(Expr::Literal(tok))
+            }
+            Token::Ident(name) => Ok(Expr::Variable(name)),
+            Token::LParen => {
+                let expr = self.parse_expr(0)?;
+                self.expect_rparen()?;
+                Ok(Expr::Grouping(Box::new(expr)))
+            }
+            _ => Err(self.error_at(loc, &tok, "Expect expression.")),
+        }
+    }
+}
+
+const UNARY_BP: u8 = 9;
+
+/// `(left, right)` binding powers for each infix operator Lox supports,
+/// lowest precedence first. Equal binding powers on both sides would make an
+/// operator right-associative; these are all left-associative, so the right
+/// side binds one tighter than the left.
+fn infix_binding_power(tok: &Token) -> Option<(u8, u8)> {
+    Some(match tok {
+        Token::EqualEqual | Token::BangEqual => (1, 2),
+        Token::Less | Token::LessEqual | Token::Greater | Token::GreaterEqual => (3, 4),
+        Token::Plus | Token::Minus => (5, 6),
+        Token::Star | Token::Slash => (7, 8),
+        _ => return None,
+    })
+}